@@ -0,0 +1,94 @@
+mod writer;
+
+pub use writer::spawn;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::ssh::ConnectionId;
+
+/// a single audit event, tagged with the connection it belongs to and when
+/// it happened
+///
+/// every meaningful action an `AppClient`/`AppChannel` handles produces one
+/// of these, which is sent over an `UnboundedSender<AuditRecord>` to the
+/// background writer task spawned by [`spawn`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub connection_id: ConnectionId,
+    /// the username this connection authenticated as, once known; `None`
+    /// for events recorded before auth succeeds (or if it never does)
+    pub user: Option<String>,
+    pub timestamp_unix_ms: u128,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+impl AuditRecord {
+    pub fn new(connection_id: ConnectionId, user: Option<String>, event: AuditEvent) -> Self {
+        let timestamp_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        AuditRecord {
+            connection_id,
+            user,
+            timestamp_unix_ms,
+            event,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    ConnectionOpened { peer_addr: String },
+    AuthAttempt { method: &'static str, user: String, accepted: bool },
+    ChannelOpenSession,
+    PtyRequest { term: String, cols: u16, rows: u16 },
+    WindowChange { cols: u16, rows: u16 },
+    Stdin { bytes: usize },
+    ExecRequest { command: String },
+    Disconnected,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_a_flat_tagged_object() {
+        let record = AuditRecord::new(
+            ConnectionId::new(),
+            Some("alice".to_string()),
+            AuditEvent::PtyRequest {
+                term: "xterm".to_string(),
+                cols: 80,
+                rows: 24,
+            },
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+        let object = value.as_object().unwrap();
+
+        assert_eq!(object["user"], "alice");
+        assert_eq!(object["event"], "pty_request");
+        assert_eq!(object["term"], "xterm");
+        assert_eq!(object["cols"], 80);
+        assert_eq!(object["rows"], 24);
+        assert!(object.contains_key("connection_id"));
+        assert!(object.contains_key("timestamp_unix_ms"));
+    }
+
+    #[test]
+    fn user_is_null_before_auth_succeeds() {
+        let record = AuditRecord::new(ConnectionId::new(), None, AuditEvent::Disconnected);
+
+        let value: serde_json::Value = serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+
+        assert!(value["user"].is_null());
+        assert_eq!(value["event"], "disconnected");
+    }
+}
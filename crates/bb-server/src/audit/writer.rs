@@ -0,0 +1,76 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use tokio::sync::mpsc::{UnboundedSender, UnboundedReceiver, unbounded_channel};
+use tokio::task::JoinHandle;
+
+use super::AuditRecord;
+
+/// spawns the background task that serializes every `AuditRecord` to JSON
+/// Lines, and returns the sender handlers should clone and use to emit events
+///
+/// writes to `path` if given, otherwise to stdout; each record is flushed as
+/// soon as it's written so operators get a live, replayable trace
+pub fn spawn(path: Option<PathBuf>) -> anyhow::Result<(UnboundedSender<AuditRecord>, JoinHandle<()>)> {
+    let sink: Box<dyn Write + Send> = match path {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+
+    let (sender, receiver) = unbounded_channel();
+    let handle = tokio::spawn(run(receiver, sink));
+    Ok((sender, handle))
+}
+
+async fn run(mut receiver: UnboundedReceiver<AuditRecord>, mut sink: Box<dyn Write + Send>) {
+    while let Some(record) = receiver.recv().await {
+        match serde_json::to_string(&record) {
+            Ok(line) => {
+                let _ = writeln!(sink, "{line}");
+                let _ = sink.flush();
+            }
+            Err(err) => eprintln!("failed to serialize audit record: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditEvent;
+    use crate::ssh::ConnectionId;
+
+    #[tokio::test]
+    async fn spawn_writes_one_json_line_per_record() {
+        let path = std::env::temp_dir().join(format!("bb-audit-writer-test-{}", uuid::Uuid::new_v4()));
+        let (sender, handle) = spawn(Some(path.clone())).unwrap();
+
+        sender
+            .send(AuditRecord::new(ConnectionId::new(), None, AuditEvent::Disconnected))
+            .unwrap();
+        sender
+            .send(AuditRecord::new(
+                ConnectionId::new(),
+                Some("alice".to_string()),
+                AuditEvent::ChannelOpenSession,
+            ))
+            .unwrap();
+
+        // dropping the sender closes the channel, letting `run`'s loop exit
+        // so the spawned task finishes
+        drop(sender);
+        handle.await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(first["event"], "disconnected");
+        assert_eq!(second["event"], "channel_open_session");
+        assert_eq!(second["user"], "alice");
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use russh::keys::PublicKey;
+use russh::server::Auth;
+
+use super::{Authenticator, reject};
+
+/// checks offered public keys against an OpenSSH `authorized_keys` file,
+/// and optionally a configured set of username/password credentials
+pub struct AuthorizedKeysAuthenticator {
+    authorized_keys: Vec<PublicKey>,
+    passwords: HashMap<String, String>,
+}
+
+impl AuthorizedKeysAuthenticator {
+    /// load `authorized_keys` in OpenSSH format
+    ///
+    /// `passwords` maps username to its expected plaintext password and may
+    /// be left empty if password auth should never succeed
+    pub fn load(authorized_keys_path: impl AsRef<Path>, passwords: HashMap<String, String>) -> anyhow::Result<Self> {
+        let path = authorized_keys_path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading authorized_keys file at {}", path.display()))?;
+
+        let authorized_keys = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(PublicKey::from_openssh)
+            .collect::<Result<Vec<_>, _>>()
+            .context("parsing authorized_keys file")?;
+
+        Ok(AuthorizedKeysAuthenticator {
+            authorized_keys,
+            passwords,
+        })
+    }
+}
+
+#[async_trait]
+impl Authenticator for AuthorizedKeysAuthenticator {
+    async fn authenticate_password(&self, user: &str, password: &str) -> Auth {
+        match self.passwords.get(user) {
+            Some(expected) if constant_time_eq(expected.as_bytes(), password.as_bytes()) => Auth::Accept,
+            _ => reject(),
+        }
+    }
+
+    async fn authenticate_publickey(&self, _user: &str, key: &PublicKey) -> Auth {
+        if self
+            .authorized_keys
+            .iter()
+            .any(|allowed| allowed.key_data() == key.key_data())
+        {
+            Auth::Accept
+        } else {
+            reject()
+        }
+    }
+}
+
+/// compares two byte strings in constant time, so a mismatching password
+/// doesn't leak how many of its leading bytes were correct through timing
+///
+/// lengths are compared up front (the length of a guess isn't the secret
+/// here), but every byte of the shorter-or-equal comparison runs regardless
+/// of where the first mismatch is
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const TEST_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAICNGSe6P/19hIXmWRxwVbue9ll/5Tn4/Stg4Tpzucq6x test@example";
+    const OTHER_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIABlmUmFLlTaHQnz8VZR/VOmtS8BAMdd2IHfTe7ws09X other@example";
+
+    fn write_authorized_keys(contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("bb-authorized-keys-test-{}", uuid::Uuid::new_v4()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn load_skips_comments_and_blank_lines() {
+        let path = write_authorized_keys(&format!("# a comment\n\n{TEST_KEY}\n"));
+        let authenticator = AuthorizedKeysAuthenticator::load(&path, HashMap::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(authenticator.authorized_keys.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn authenticate_publickey_accepts_a_listed_key() {
+        let path = write_authorized_keys(TEST_KEY);
+        let authenticator = AuthorizedKeysAuthenticator::load(&path, HashMap::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let key = PublicKey::from_openssh(TEST_KEY).unwrap();
+        assert!(matches!(
+            authenticator.authenticate_publickey("user", &key).await,
+            Auth::Accept
+        ));
+    }
+
+    #[tokio::test]
+    async fn authenticate_publickey_rejects_an_unlisted_key() {
+        let path = write_authorized_keys(TEST_KEY);
+        let authenticator = AuthorizedKeysAuthenticator::load(&path, HashMap::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let key = PublicKey::from_openssh(OTHER_KEY).unwrap();
+        assert!(!matches!(
+            authenticator.authenticate_publickey("user", &key).await,
+            Auth::Accept
+        ));
+    }
+
+    #[tokio::test]
+    async fn authenticate_password_checks_the_configured_user() {
+        let path = write_authorized_keys("");
+        let passwords = HashMap::from([("alice".to_string(), "correct horse".to_string())]);
+        let authenticator = AuthorizedKeysAuthenticator::load(&path, passwords).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            authenticator.authenticate_password("alice", "correct horse").await,
+            Auth::Accept
+        ));
+        assert!(!matches!(
+            authenticator.authenticate_password("alice", "wrong").await,
+            Auth::Accept
+        ));
+        assert!(!matches!(
+            authenticator.authenticate_password("bob", "correct horse").await,
+            Auth::Accept
+        ));
+    }
+
+    #[test]
+    fn constant_time_eq_matches_identical_bytes() {
+        assert!(constant_time_eq(b"hunter2", b"hunter2"));
+    }
+
+    #[test]
+    fn constant_time_eq_rejects_different_bytes() {
+        assert!(!constant_time_eq(b"hunter2", b"hunter3"));
+    }
+
+    #[test]
+    fn constant_time_eq_rejects_different_lengths() {
+        assert!(!constant_time_eq(b"hunter2", b"hunter22"));
+    }
+}
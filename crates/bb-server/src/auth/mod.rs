@@ -0,0 +1,48 @@
+mod authorized_keys;
+
+pub use authorized_keys::AuthorizedKeysAuthenticator;
+
+use async_trait::async_trait;
+use russh::keys::PublicKey;
+use russh::server::Auth;
+
+/// decides whether a connecting client is allowed in, for each auth method
+/// the server offers
+///
+/// kept as a trait, injected into `AppClient`, so the authentication
+/// backend can be swapped out (an `authorized_keys` file today, an external
+/// identity provider tomorrow) without touching the handler; `async_trait`
+/// is used rather than a native `async fn` so the trait stays object-safe
+/// and implementations can hit an external store
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    async fn authenticate_password(&self, user: &str, password: &str) -> Auth;
+
+    async fn authenticate_publickey(&self, user: &str, key: &PublicKey) -> Auth;
+}
+
+/// the safe-by-default authenticator: rejects every attempt
+///
+/// used whenever `AppClient` is constructed without an explicit
+/// `Authenticator`, so forgetting to wire one in closes the server instead
+/// of opening it
+#[derive(Default)]
+pub struct DenyAllAuthenticator;
+
+#[async_trait]
+impl Authenticator for DenyAllAuthenticator {
+    async fn authenticate_password(&self, _user: &str, _password: &str) -> Auth {
+        reject()
+    }
+
+    async fn authenticate_publickey(&self, _user: &str, _key: &PublicKey) -> Auth {
+        reject()
+    }
+}
+
+/// a plain rejection, with no further methods offered
+fn reject() -> Auth {
+    Auth::Reject {
+        proceed_with_methods: None,
+    }
+}
@@ -0,0 +1,6 @@
+pub mod audit;
+pub mod auth;
+mod server;
+pub mod ssh;
+
+pub use server::BbServer;
@@ -0,0 +1,45 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use russh::server::Server as RusshServer;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::audit::{AuditEvent, AuditRecord};
+use crate::auth::Authenticator;
+use crate::ssh::{AppClient, ConnectionId};
+
+/// the top-level `russh` server
+///
+/// constructs a fresh `AppClient` for every accepted tcp connection, wiring
+/// in the shared authenticator and audit log sender so every connection
+/// reports through the same backends
+#[derive(Clone)]
+pub struct BbServer {
+    authenticator: Arc<dyn Authenticator>,
+    audit: UnboundedSender<AuditRecord>,
+}
+
+impl BbServer {
+    pub fn new(authenticator: Arc<dyn Authenticator>, audit: UnboundedSender<AuditRecord>) -> Self {
+        BbServer { authenticator, audit }
+    }
+}
+
+impl RusshServer for BbServer {
+    type Handler = AppClient;
+
+    fn new_client(&mut self, peer_addr: Option<SocketAddr>) -> AppClient {
+        let connection_id = ConnectionId::new();
+        let peer_addr = peer_addr
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let _ = self.audit.send(AuditRecord::new(
+            connection_id,
+            None,
+            AuditEvent::ConnectionOpened { peer_addr },
+        ));
+
+        AppClient::new(connection_id, self.authenticator.clone(), self.audit.clone())
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::{self, Write};
+
+use anyhow::Context;
+use bb_tui::{App, AppEvent, AppKey, InputDecoder, commands};
+use ratatui::layout::Rect;
+use russh::ChannelId;
+use russh::server::Handle;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
+
+use super::connection::ConnectionId;
+use super::registry::ClientRegistry;
+
+/// writes rendered terminal frames back to the remote client over the ssh
+/// channel
+///
+/// `write` only buffers the drawn bytes; `flush` hands the finished frame
+/// off to [`run_writer`] over a `watch` channel rather than writing to the
+/// wire itself, so `std::io::Write::flush` never blocks the executor on the
+/// async `Handle::data` call
+struct ChannelWriter {
+    frame_sender: watch::Sender<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        // a `watch` channel only ever holds its latest value, so a writer
+        // task that falls behind a burst of frames naturally coalesces
+        // down to the newest one instead of the buffer growing unbounded
+        let data = std::mem::take(&mut self.buffer);
+        let _ = self.frame_sender.send(data);
+        Ok(())
+    }
+}
+
+/// owns the russh channel handle and writes out each frame drawn by
+/// [`ChannelWriter`], one connection's whole lifetime
+///
+/// a slow client simply means this task is slow to reach the next
+/// `changed()`; frames sent in the meantime overwrite each other in the
+/// `watch` channel rather than queuing up, so the connection applies
+/// backpressure by dropping intermediate frames instead of buffering them
+async fn run_writer(handle: Handle, channel_id: ChannelId, mut frame_receiver: watch::Receiver<Vec<u8>>) {
+    while frame_receiver.changed().await.is_ok() {
+        let frame = frame_receiver.borrow_and_update().clone();
+        if handle.data(channel_id, frame.into()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// the application channel opened for a single connected client
+///
+/// owns this connection's `bb_tui::App` once a pty has been requested, and
+/// decodes raw stdin bytes into `AppEvent::Key`s for it; the application
+/// itself renders from state shared with every other connected client via
+/// `ClientRegistry`
+pub struct AppChannel {
+    connection_id: ConnectionId,
+    channel_id: ChannelId,
+    input_decoder: InputDecoder,
+    registry: ClientRegistry,
+    username: Option<String>,
+    event_sender: Option<UnboundedSender<AppEvent>>,
+}
+
+impl AppChannel {
+    pub fn new(channel_id: ChannelId, connection_id: ConnectionId, username: Option<String>) -> Self {
+        AppChannel {
+            connection_id,
+            channel_id,
+            input_decoder: InputDecoder::new(),
+            registry: ClientRegistry::global().clone(),
+            username,
+            event_sender: None,
+        }
+    }
+
+    /// creates the underlying `App`, sized to the requested pty, registers
+    /// it (and the authenticated username, if any) with the shared
+    /// `ClientRegistry` and spawns its main loop
+    pub async fn create_pty(&mut self, handle: Handle, cols: u16, rows: u16) -> anyhow::Result<()> {
+        let (frame_sender, frame_receiver) = watch::channel(Vec::new());
+        let writer = ChannelWriter {
+            frame_sender,
+            buffer: Vec::new(),
+        };
+        let (app, event_sender) = App::new(writer, self.registry.state())?;
+
+        let viewport = Rect::new(0, 0, cols, rows);
+        self.registry
+            .register(self.connection_id, event_sender.clone(), viewport, self.username.clone());
+
+        tokio::spawn(run_writer(handle, self.channel_id, frame_receiver));
+        event_sender.send(AppEvent::Resize((cols, rows)))?;
+        self.event_sender = Some(event_sender);
+        tokio::spawn(app.run());
+        Ok(())
+    }
+
+    /// decodes a chunk of raw stdin bytes, applies any decoded keys to the
+    /// shared state right away, and nudges every connected client (this one
+    /// included) to redraw
+    ///
+    /// the state mutation happens here rather than being handed to this
+    /// connection's own `App` via its event queue, so it's guaranteed to be
+    /// visible to every other client by the time their `Render` is sent;
+    /// routing it through the queue instead could let another client's
+    /// redraw run before this connection's queued key was applied
+    ///
+    /// `Enter` runs the buffered command line through the same
+    /// `bb_tui::commands` registry the non-interactive `exec` path uses, so
+    /// the two never drift apart
+    pub async fn stdin(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.event_sender
+            .as_ref()
+            .context("expected `create_pty` to already be called")?;
+
+        let keys = self.input_decoder.feed(data);
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let state = self.registry.state();
+        let user = self.registry.username(self.connection_id);
+        for key in keys {
+            state.set_last_key(key, user.clone());
+            match key {
+                AppKey::Char(c) => state.push_command_char(c),
+                AppKey::Backspace => state.backspace_command_char(),
+                AppKey::Enter => {
+                    let command_line = state.take_command_line();
+                    let output = commands::dispatch(&command_line).await;
+                    state.set_last_command_output(String::from_utf8_lossy(&output.stdout).into_owned());
+                }
+                _ => {}
+            }
+        }
+        self.registry.broadcast_render();
+        Ok(())
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> anyhow::Result<()> {
+        let event_sender = self
+            .event_sender
+            .as_ref()
+            .context("expected `create_pty` to already be called")?;
+        self.registry
+            .update_viewport(self.connection_id, Rect::new(0, 0, cols, rows));
+        event_sender.send(AppEvent::Resize((cols, rows)))?;
+        Ok(())
+    }
+}
+
+impl Drop for AppChannel {
+    fn drop(&mut self) {
+        self.registry.deregister(self.connection_id);
+    }
+}
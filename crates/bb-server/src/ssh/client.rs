@@ -1,8 +1,17 @@
+use std::sync::Arc;
+
 use anyhow::Context;
 use russh::keys::PublicKey;
 use russh::server::{Auth, Handler, Msg, Session};
-use russh::{Channel, ChannelId, Pty};
+use russh::{Channel, ChannelId, MethodSet, Pty};
+use tokio::sync::mpsc::UnboundedSender;
+
+use bb_tui::commands;
 
+use crate::audit::{AuditEvent, AuditRecord};
+use crate::auth::{Authenticator, DenyAllAuthenticator};
+
+use super::ConnectionId;
 use super::channel::AppChannel;
 
 /// a simple macro that will return to the client the action status
@@ -22,9 +31,53 @@ macro_rules! channel_action_with_state {
 ///
 /// the `AppClient` will forward the connection events to the correct
 /// channel methods for the channel to handle correctly
-#[derive(Default)]
 pub struct AppClient {
+    connection_id: ConnectionId,
     app_channel: Option<AppChannel>,
+    authenticator: Arc<dyn Authenticator>,
+    audit: UnboundedSender<AuditRecord>,
+    /// set once an auth attempt for this connection has succeeded
+    username: Option<String>,
+}
+
+impl AppClient {
+    pub fn new(
+        connection_id: ConnectionId,
+        authenticator: Arc<dyn Authenticator>,
+        audit: UnboundedSender<AuditRecord>,
+    ) -> Self {
+        AppClient {
+            connection_id,
+            app_channel: None,
+            authenticator,
+            audit,
+            username: None,
+        }
+    }
+
+    /// records one audit event for this connection; failures to deliver it
+    /// (writer task gone) are not fatal to the connection itself
+    fn emit_audit(&self, event: AuditEvent) {
+        let _ = self
+            .audit
+            .send(AuditRecord::new(self.connection_id, self.username.clone(), event));
+    }
+}
+
+impl Default for AppClient {
+    /// defaults to `DenyAllAuthenticator` and a discarded audit sender, so a
+    /// client built without explicit wiring fails closed rather than wide
+    /// open and never panics for lack of a writer
+    fn default() -> Self {
+        let (audit, _) = tokio::sync::mpsc::unbounded_channel();
+        AppClient::new(ConnectionId::new(), Arc::new(DenyAllAuthenticator), audit)
+    }
+}
+
+impl Drop for AppClient {
+    fn drop(&mut self) {
+        self.emit_audit(AuditEvent::Disconnected);
+    }
 }
 
 impl Handler for AppClient {
@@ -39,7 +92,8 @@ impl Handler for AppClient {
             anyhow::bail!("only a single session channel can be created");
         }
 
-        self.app_channel = Some(AppChannel::new(channel.id()));
+        self.emit_audit(AuditEvent::ChannelOpenSession);
+        self.app_channel = Some(AppChannel::new(channel.id(), self.connection_id, self.username.clone()));
         Ok(true)
     }
 
@@ -49,6 +103,7 @@ impl Handler for AppClient {
         data: &[u8],
         session: &mut Session,
     ) -> anyhow::Result<()> {
+        self.emit_audit(AuditEvent::Stdin { bytes: data.len() });
         let app_channel = self
             .app_channel
             .as_mut()
@@ -60,7 +115,7 @@ impl Handler for AppClient {
     async fn pty_request(
         &mut self,
         channel: ChannelId,
-        _: &str,
+        term: &str,
         col_width: u32,
         row_height: u32,
         _: u32,
@@ -68,6 +123,11 @@ impl Handler for AppClient {
         _: &[(Pty, u32)],
         session: &mut Session,
     ) -> anyhow::Result<()> {
+        self.emit_audit(AuditEvent::PtyRequest {
+            term: term.to_string(),
+            cols: col_width as u16,
+            rows: row_height as u16,
+        });
         let app_channel = self
             .app_channel
             .as_mut()
@@ -91,6 +151,10 @@ impl Handler for AppClient {
         _: u32,
         session: &mut Session,
     ) -> anyhow::Result<()> {
+        self.emit_audit(AuditEvent::WindowChange {
+            cols: col_width as u16,
+            rows: row_height as u16,
+        });
         let app_channel = self
             .app_channel
             .as_ref()
@@ -103,15 +167,69 @@ impl Handler for AppClient {
         Ok(())
     }
 
-    async fn auth_none(&mut self, _: &str) -> anyhow::Result<Auth> {
-        Ok(Auth::Accept)
+    /// handles a non-interactive `ssh host <command>` request: no pty or
+    /// `AppChannel` is involved, the command is dispatched straight to the
+    /// shared command registry and its output written back before the
+    /// channel is torn down
+    async fn exec_request(
+        &mut self,
+        channel: ChannelId,
+        data: &[u8],
+        session: &mut Session,
+    ) -> anyhow::Result<()> {
+        let command_line = String::from_utf8_lossy(data).into_owned();
+        self.emit_audit(AuditEvent::ExecRequest {
+            command: command_line.clone(),
+        });
+        session.channel_success(channel)?;
+
+        let output = commands::dispatch(&command_line).await;
+        session.data(channel, output.stdout.into());
+        session.exit_status_request(channel, output.exit_code);
+        session.eof(channel);
+        session.close(channel);
+        Ok(())
+    }
+
+    async fn auth_none(&mut self, user: &str) -> anyhow::Result<Auth> {
+        self.emit_audit(AuditEvent::AuthAttempt {
+            method: "none",
+            user: user.to_string(),
+            accepted: false,
+        });
+
+        // never accept without credentials; tell the client which methods
+        // it can still try instead of silently letting it in
+        Ok(Auth::Reject {
+            proceed_with_methods: Some(MethodSet::PUBLICKEY | MethodSet::PASSWORD),
+        })
     }
 
-    async fn auth_password(&mut self, _: &str, _: &str) -> anyhow::Result<Auth> {
-        Ok(Auth::Accept)
+    async fn auth_password(&mut self, user: &str, password: &str) -> anyhow::Result<Auth> {
+        let auth = self.authenticator.authenticate_password(user, password).await;
+        let accepted = matches!(auth, Auth::Accept);
+        if accepted {
+            self.username = Some(user.to_string());
+        }
+        self.emit_audit(AuditEvent::AuthAttempt {
+            method: "password",
+            user: user.to_string(),
+            accepted,
+        });
+        Ok(auth)
     }
 
-    async fn auth_publickey(&mut self, _: &str, _: &PublicKey) -> anyhow::Result<Auth> {
-        Ok(Auth::Accept)
+    async fn auth_publickey(&mut self, user: &str, key: &PublicKey) -> anyhow::Result<Auth> {
+        let auth = self.authenticator.authenticate_publickey(user, key).await;
+        let accepted = matches!(auth, Auth::Accept);
+        if accepted {
+            self.username = Some(user.to_string());
+        }
+        self.emit_audit(AuditEvent::AuthAttempt {
+            method: "publickey",
+            user: user.to_string(),
+            accepted,
+        });
+        Ok(auth)
     }
 }
@@ -0,0 +1,27 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// a unique identifier assigned to each accepted ssh connection
+///
+/// used to correlate registry membership and audit log entries belonging
+/// to the same client across the lifetime of the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct ConnectionId(Uuid);
+
+impl ConnectionId {
+    pub fn new() -> Self {
+        ConnectionId(Uuid::new_v4())
+    }
+}
+
+impl Default for ConnectionId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,7 @@
+mod channel;
+mod client;
+mod connection;
+mod registry;
+
+pub use client::AppClient;
+pub(crate) use connection::ConnectionId;
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bb_tui::{AppEvent, SharedAppState};
+use ratatui::layout::Rect;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::connection::ConnectionId;
+
+struct ClientEntry {
+    event_sender: UnboundedSender<AppEvent>,
+    viewport: Rect,
+    username: Option<String>,
+}
+
+/// tracks every client currently connected to the collaborative application
+///
+/// each connection keeps its own `Terminal`, but all of them render the
+/// same `SharedAppState`; when one connection's input mutates that state,
+/// the registry is used to nudge every connection's event loop into
+/// redrawing so the change is visible to all of them, live
+#[derive(Clone)]
+pub struct ClientRegistry {
+    clients: Arc<Mutex<HashMap<ConnectionId, ClientEntry>>>,
+    state: SharedAppState,
+}
+
+impl ClientRegistry {
+    /// the single registry shared by every connection accepted by this
+    /// process, so that the collaborative application has exactly one copy
+    /// of shared state no matter how many clients join
+    pub fn global() -> &'static ClientRegistry {
+        static REGISTRY: OnceLock<ClientRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| ClientRegistry {
+            clients: Arc::default(),
+            state: SharedAppState::default(),
+        })
+    }
+
+    pub fn state(&self) -> SharedAppState {
+        self.state.clone()
+    }
+
+    pub fn register(
+        &self,
+        id: ConnectionId,
+        event_sender: UnboundedSender<AppEvent>,
+        viewport: Rect,
+        username: Option<String>,
+    ) {
+        self.clients.lock().unwrap().insert(
+            id,
+            ClientEntry {
+                event_sender,
+                viewport,
+                username,
+            },
+        );
+    }
+
+    pub fn deregister(&self, id: ConnectionId) {
+        self.clients.lock().unwrap().remove(&id);
+    }
+
+    /// the username authenticated for a connection, if any; lets callers
+    /// attribute a mutation to the user who made it
+    pub fn username(&self, id: ConnectionId) -> Option<String> {
+        self.clients.lock().unwrap().get(&id).and_then(|entry| entry.username.clone())
+    }
+
+    pub fn update_viewport(&self, id: ConnectionId, viewport: Rect) {
+        if let Some(entry) = self.clients.lock().unwrap().get_mut(&id) {
+            entry.viewport = viewport;
+        }
+    }
+
+    /// push a redraw to every registered client, including the one whose
+    /// input triggered it; state mutations are applied synchronously before
+    /// this is called, so every client (this one too) renders fresh state
+    pub fn broadcast_render(&self) {
+        for entry in self.clients.lock().unwrap().values() {
+            let _ = entry.event_sender.send(AppEvent::Render);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ClientRegistry {
+        ClientRegistry {
+            clients: Arc::default(),
+            state: SharedAppState::default(),
+        }
+    }
+
+    #[test]
+    fn register_then_broadcast_reaches_the_client() {
+        let registry = registry();
+        let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+        registry.register(ConnectionId::new(), event_sender, Rect::default(), None);
+
+        registry.broadcast_render();
+
+        assert!(matches!(event_receiver.try_recv(), Ok(AppEvent::Render)));
+    }
+
+    #[test]
+    fn deregister_stops_future_broadcasts() {
+        let registry = registry();
+        let id = ConnectionId::new();
+        let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+        registry.register(id, event_sender, Rect::default(), None);
+
+        registry.deregister(id);
+        registry.broadcast_render();
+
+        assert!(event_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn username_returns_the_registered_connections_user() {
+        let registry = registry();
+        let id = ConnectionId::new();
+        let (event_sender, _event_receiver) = tokio::sync::mpsc::unbounded_channel();
+        registry.register(id, event_sender, Rect::default(), Some("alice".to_string()));
+
+        assert_eq!(registry.username(id), Some("alice".to_string()));
+        assert_eq!(registry.username(ConnectionId::new()), None);
+    }
+
+    #[test]
+    fn update_viewport_changes_the_stored_entry() {
+        let registry = registry();
+        let id = ConnectionId::new();
+        let (event_sender, _event_receiver) = tokio::sync::mpsc::unbounded_channel();
+        registry.register(id, event_sender, Rect::default(), None);
+
+        registry.update_viewport(id, Rect::new(0, 0, 80, 24));
+
+        let clients = registry.clients.lock().unwrap();
+        assert_eq!(clients.get(&id).unwrap().viewport, Rect::new(0, 0, 80, 24));
+    }
+}
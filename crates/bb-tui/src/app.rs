@@ -8,7 +8,10 @@ use ratatui::{Terminal, TerminalOptions};
 
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 
+use crate::commands;
 use crate::events::AppEvent;
+use crate::input::AppKey;
+use crate::state::SharedAppState;
 
 pub struct App<W>
 where
@@ -16,15 +19,20 @@ where
 {
     event_receiver: UnboundedReceiver<AppEvent>,
     terminal: Terminal<CrosstermBackend<W>>,
+    state: SharedAppState,
 }
 
 impl<W> App<W>
 where
     W: Write,
 {
-    /// creates a new application instance and will return the app event sender
-    /// to the caller so he can send calls from outside into the application
-    pub fn new(stdout: W) -> anyhow::Result<(Self, UnboundedSender<AppEvent>)> {
+    /// creates a new application instance bound to `state` and will return
+    /// the app event sender to the caller so he can send calls from outside
+    /// into the application
+    ///
+    /// `state` may be shared with other `App` instances so that several
+    /// connections can drive and observe the same logical application
+    pub fn new(stdout: W, state: SharedAppState) -> anyhow::Result<(Self, UnboundedSender<AppEvent>)> {
         let (event_sender, event_receiver) = unbounded_channel();
         let terminal = {
             let backend = CrosstermBackend::new(stdout);
@@ -37,6 +45,7 @@ where
         let app = App {
             event_receiver,
             terminal,
+            state,
         };
         Ok((app, event_sender))
     }
@@ -60,16 +69,55 @@ where
                     // the eventloop to comeback to use to handle that event
                     self.render()?;
                 }
-                _ => unimplemented!(),
+                AppEvent::Key(key) => {
+                    // this connection's own username is unknown here; callers that
+                    // can attribute the key to a user (e.g. `AppChannel::stdin`)
+                    // mutate `SharedAppState` directly instead of going through
+                    // this event
+                    self.state.set_last_key(key, None);
+
+                    // mirror `AppChannel::stdin`'s command-line handling so a
+                    // caller driving the app through this event sees the same
+                    // behavior as the ssh path
+                    match key {
+                        AppKey::Char(c) => self.state.push_command_char(c),
+                        AppKey::Backspace => self.state.backspace_command_char(),
+                        AppKey::Enter => {
+                            let command_line = self.state.take_command_line();
+                            let output = commands::dispatch(&command_line).await;
+                            self.state
+                                .set_last_command_output(String::from_utf8_lossy(&output.stdout).into_owned());
+                        }
+                        _ => {}
+                    }
+
+                    // redraw immediately so the keypress is visibly reflected,
+                    // same as the resize path above
+                    self.render()?;
+                }
             }
         }
         Ok(())
     }
 
     fn render(&mut self) -> anyhow::Result<()> {
+        let last_key = self.state.last_key();
+        let last_key_by = self.state.last_key_by();
+        let command_line = self.state.command_line();
+        let last_command_output = self.state.last_command_output();
         self.terminal.draw(|frame| {
             let block = Block::bordered();
-            let p = Paragraph::new("hello world").block(block);
+            let greeting = match (last_key, last_key_by) {
+                (Some(key), Some(user)) => format!("hello world ({key:?} by {user})"),
+                (Some(key), None) => format!("hello world ({key:?})"),
+                (None, _) => "hello world".to_string(),
+            };
+            let mut text = format!("{greeting}\n> {command_line}");
+            if let Some(output) = last_command_output {
+                text.push('\n');
+                text.push_str(output.trim_end_matches('\n'));
+            }
+            let p = Paragraph::new(text).block(block);
             frame.render_widget(p, frame.area());
         })?;
         Ok(())
@@ -0,0 +1,100 @@
+/// the result of running a command: the bytes it would have written to
+/// stdout, and the exit code to report back to the client
+pub struct CommandOutput {
+    pub stdout: Vec<u8>,
+    pub exit_code: u32,
+}
+
+impl CommandOutput {
+    fn ok(stdout: impl Into<Vec<u8>>) -> Self {
+        CommandOutput {
+            stdout: stdout.into(),
+            exit_code: 0,
+        }
+    }
+}
+
+/// splits a raw command line into a name and its arguments and runs it, the
+/// same way a shell would hand a parsed command line to [`run`]
+///
+/// argument splitting is plain whitespace-separated words; there is no
+/// quoting support yet since none of the registered commands need it
+pub async fn dispatch(command_line: &str) -> CommandOutput {
+    let mut words = command_line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let args: Vec<String> = words.map(str::to_string).collect();
+    run(name, &args).await
+}
+
+/// runs a named command and returns its output
+///
+/// this is the single place command dispatch happens, reachable both from
+/// a non-interactive `exec` request and from inside the interactive
+/// `App`, so the two never drift apart
+pub async fn run(name: &str, args: &[String]) -> CommandOutput {
+    match name {
+        "echo" => echo(args),
+        "whoami" => whoami(args),
+        "" => CommandOutput::ok(""),
+        _ => CommandOutput {
+            stdout: format!("bb: unknown command: {name}\n").into_bytes(),
+            exit_code: 127,
+        },
+    }
+}
+
+fn echo(args: &[String]) -> CommandOutput {
+    let mut stdout = args.join(" ").into_bytes();
+    stdout.push(b'\n');
+    CommandOutput::ok(stdout)
+}
+
+fn whoami(_args: &[String]) -> CommandOutput {
+    CommandOutput::ok("bb\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn echo_joins_args_with_spaces() {
+        let output = run("echo", &["hello".to_string(), "world".to_string()]).await;
+        assert_eq!(output.stdout, b"hello world\n");
+        assert_eq!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn whoami_ignores_its_args() {
+        let output = run("whoami", &["anything".to_string()]).await;
+        assert_eq!(output.stdout, b"bb\n");
+        assert_eq!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn unknown_command_exits_127() {
+        let output = run("nope", &[]).await;
+        assert_eq!(output.stdout, b"bb: unknown command: nope\n");
+        assert_eq!(output.exit_code, 127);
+    }
+
+    #[tokio::test]
+    async fn empty_command_line_is_a_no_op() {
+        let output = run("", &[]).await;
+        assert_eq!(output.stdout, b"");
+        assert_eq!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn dispatch_splits_the_command_line_on_whitespace() {
+        let output = dispatch("echo  hello   world").await;
+        assert_eq!(output.stdout, b"hello world\n");
+    }
+
+    #[tokio::test]
+    async fn dispatch_of_blank_input_runs_the_empty_command() {
+        let output = dispatch("   ").await;
+        assert_eq!(output.stdout, b"");
+        assert_eq!(output.exit_code, 0);
+    }
+}
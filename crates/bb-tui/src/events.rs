@@ -0,0 +1,10 @@
+use crate::input::AppKey;
+
+/// events produced outside of the application that drive its state, sent
+/// through the channel returned from `App::new`
+#[derive(Debug, Clone, Copy)]
+pub enum AppEvent {
+    Render,
+    Resize((u16, u16)),
+    Key(AppKey),
+}
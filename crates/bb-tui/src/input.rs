@@ -0,0 +1,186 @@
+use std::str;
+
+/// a single decoded keypress, produced by `InputDecoder` from the raw
+/// stdin byte stream of a connected terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppKey {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+}
+
+/// incrementally decodes a raw terminal byte stream into `AppKey`s
+///
+/// ssh hands stdin to `data` in arbitrary chunk boundaries, so a multi-byte
+/// utf-8 character or a csi escape sequence can be split across two calls;
+/// whatever is left incomplete at the end of a chunk is kept in `pending`
+/// until the remaining bytes arrive
+#[derive(Debug, Default)]
+pub struct InputDecoder {
+    pending: Vec<u8>,
+}
+
+impl InputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// feed a freshly received chunk and return the key events it produced
+    pub fn feed(&mut self, data: &[u8]) -> Vec<AppKey> {
+        self.pending.extend_from_slice(data);
+
+        let mut keys = Vec::new();
+        let mut consumed = 0;
+
+        while consumed < self.pending.len() {
+            match decode_one(&self.pending[consumed..]) {
+                Decoded::Key(key, len) => {
+                    keys.push(key);
+                    consumed += len;
+                }
+                Decoded::Invalid(len) => consumed += len,
+                Decoded::Incomplete => break,
+            }
+        }
+
+        self.pending.drain(..consumed);
+        keys
+    }
+}
+
+enum Decoded {
+    Key(AppKey, usize),
+    Invalid(usize),
+    Incomplete,
+}
+
+fn decode_one(bytes: &[u8]) -> Decoded {
+    match bytes[0] {
+        0x1b => decode_escape(bytes),
+        0x0d | 0x0a => Decoded::Key(AppKey::Enter, 1),
+        0x09 => Decoded::Key(AppKey::Tab, 1),
+        0x7f | 0x08 => Decoded::Key(AppKey::Backspace, 1),
+        0x03 => Decoded::Key(AppKey::Ctrl('c'), 1),
+        ctrl @ 0x01..=0x1a => Decoded::Key(AppKey::Ctrl((ctrl + 0x60) as char), 1),
+        _ => decode_utf8(bytes),
+    }
+}
+
+fn decode_escape(bytes: &[u8]) -> Decoded {
+    if bytes.len() < 2 {
+        return Decoded::Incomplete;
+    }
+    if bytes[1] != b'[' {
+        return Decoded::Key(AppKey::Esc, 1);
+    }
+    if bytes.len() < 3 {
+        return Decoded::Incomplete;
+    }
+
+    match bytes[2] {
+        b'A' => Decoded::Key(AppKey::Up, 3),
+        b'B' => Decoded::Key(AppKey::Down, 3),
+        b'C' => Decoded::Key(AppKey::Right, 3),
+        b'D' => Decoded::Key(AppKey::Left, 3),
+        b'H' => Decoded::Key(AppKey::Home, 3),
+        b'F' => Decoded::Key(AppKey::End, 3),
+        b'3' | b'5' | b'6' => {
+            if bytes.len() < 4 {
+                return Decoded::Incomplete;
+            }
+            if bytes[3] != b'~' {
+                return Decoded::Invalid(4);
+            }
+            let key = match bytes[2] {
+                b'3' => AppKey::Delete,
+                b'5' => AppKey::PageUp,
+                b'6' => AppKey::PageDown,
+                _ => unreachable!(),
+            };
+            Decoded::Key(key, 4)
+        }
+        _ => Decoded::Invalid(3),
+    }
+}
+
+fn decode_utf8(bytes: &[u8]) -> Decoded {
+    let width = utf8_width(bytes[0]);
+    if bytes.len() < width {
+        return Decoded::Incomplete;
+    }
+    match str::from_utf8(&bytes[..width]).ok().and_then(|s| s.chars().next()) {
+        Some(c) => Decoded::Key(AppKey::Char(c), width),
+        None => Decoded::Invalid(1),
+    }
+}
+
+fn utf8_width(first_byte: u8) -> usize {
+    match first_byte {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_printable_ascii() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.feed(b"hi"), vec![AppKey::Char('h'), AppKey::Char('i')]);
+    }
+
+    #[test]
+    fn decodes_control_bytes() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.feed(&[0x03]), vec![AppKey::Ctrl('c')]);
+        assert_eq!(decoder.feed(&[0x0d]), vec![AppKey::Enter]);
+        assert_eq!(decoder.feed(&[0x09]), vec![AppKey::Tab]);
+        assert_eq!(decoder.feed(&[0x7f]), vec![AppKey::Backspace]);
+    }
+
+    #[test]
+    fn decodes_csi_sequences() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.feed(b"\x1b[A"), vec![AppKey::Up]);
+        assert_eq!(decoder.feed(b"\x1b[3~"), vec![AppKey::Delete]);
+    }
+
+    #[test]
+    fn buffers_incomplete_sequence_across_chunks() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.feed(b"\x1b["), vec![]);
+        assert_eq!(decoder.feed(b"A"), vec![AppKey::Up]);
+    }
+
+    #[test]
+    fn buffers_incomplete_utf8_across_chunks() {
+        let mut decoder = InputDecoder::new();
+        let euro = '\u{20ac}'.to_string().into_bytes();
+        assert_eq!(decoder.feed(&euro[..1]), vec![]);
+        assert_eq!(decoder.feed(&euro[1..]), vec![AppKey::Char('\u{20ac}')]);
+    }
+
+    #[test]
+    fn lone_trailing_esc_is_retained() {
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.feed(b"\x1b"), vec![]);
+        assert_eq!(decoder.feed(b"x"), vec![AppKey::Esc, AppKey::Char('x')]);
+    }
+}
@@ -0,0 +1,10 @@
+mod app;
+pub mod commands;
+mod events;
+mod input;
+mod state;
+
+pub use app::App;
+pub use events::AppEvent;
+pub use input::{AppKey, InputDecoder};
+pub use state::SharedAppState;
@@ -0,0 +1,69 @@
+use std::sync::{Arc, Mutex};
+
+use crate::input::AppKey;
+
+/// the logical application model, independent of any single connection's
+/// `Terminal`
+///
+/// several connections can hold a `SharedAppState` pointing at the same
+/// `AppState`, which is what lets them collaboratively drive one
+/// application: a mutation from any connection is visible to all the
+/// others as soon as they next render
+#[derive(Debug, Default)]
+pub struct AppState {
+    pub last_key: Option<AppKey>,
+    pub last_key_by: Option<String>,
+    /// the command line typed so far, accumulated one `AppKey::Char` at a
+    /// time and drained by `take_command_line` on `Enter`
+    pub command_line: String,
+    /// stdout of the last command run through [`crate::commands`]
+    pub last_command_output: Option<String>,
+}
+
+/// a cheaply cloneable handle to a shared `AppState`
+#[derive(Clone, Default)]
+pub struct SharedAppState(Arc<Mutex<AppState>>);
+
+impl SharedAppState {
+    /// `user` is the username that drove `key`, if the connection that sent
+    /// it authenticated as one, so renders can attribute the change
+    pub fn set_last_key(&self, key: AppKey, user: Option<String>) {
+        let mut state = self.0.lock().unwrap();
+        state.last_key = Some(key);
+        state.last_key_by = user;
+    }
+
+    pub fn last_key(&self) -> Option<AppKey> {
+        self.0.lock().unwrap().last_key
+    }
+
+    pub fn last_key_by(&self) -> Option<String> {
+        self.0.lock().unwrap().last_key_by.clone()
+    }
+
+    pub fn push_command_char(&self, c: char) {
+        self.0.lock().unwrap().command_line.push(c);
+    }
+
+    pub fn backspace_command_char(&self) {
+        self.0.lock().unwrap().command_line.pop();
+    }
+
+    /// drains the buffered command line, e.g. when `Enter` is pressed and
+    /// it's about to be run through [`crate::commands::dispatch`]
+    pub fn take_command_line(&self) -> String {
+        std::mem::take(&mut self.0.lock().unwrap().command_line)
+    }
+
+    pub fn command_line(&self) -> String {
+        self.0.lock().unwrap().command_line.clone()
+    }
+
+    pub fn set_last_command_output(&self, output: String) {
+        self.0.lock().unwrap().last_command_output = Some(output);
+    }
+
+    pub fn last_command_output(&self) -> Option<String> {
+        self.0.lock().unwrap().last_command_output.clone()
+    }
+}